@@ -17,7 +17,6 @@
 use snarkvm::{
     algorithms::CRH,
     dpc::{
-        testnet1::{Testnet1DPC, Testnet1Parameters},
         Address,
         DPCScheme,
         Parameters,
@@ -26,50 +25,159 @@ use snarkvm::{
         Record,
         RecordScheme,
         TransactionAuthorization as TransactionAuthorizationNative,
+        DPC,
         *,
     },
-    utilities::{to_bytes_le, ToBytes},
+    utilities::{to_bytes_le, FromBytes, ToBytes},
 };
 
 use rand::{CryptoRng, Rng};
-use std::{fmt, str::FromStr};
+use serde::{
+    de::Error as SerdeError,
+    ser::SerializeStruct,
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use std::{fmt, marker::PhantomData, str::FromStr};
 
 #[derive(Clone, Debug)]
-pub struct TransactionInput {
-    pub(crate) private_key: PrivateKey<Testnet1Parameters>,
-    pub(crate) record: Record<Testnet1Parameters>,
+pub struct TransactionInput<P: Parameters> {
+    pub(crate) private_key: PrivateKey<P>,
+    pub(crate) record: Record<P>,
+}
+
+impl<P: Parameters> Serialize for TransactionInput<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TransactionInput", 2)?;
+        state.serialize_field("private_key", &self.private_key.to_string())?;
+        state.serialize_field("record", &self.record.to_string())?;
+        state.end()
+    }
+}
+
+impl<'de, P: Parameters> Deserialize<'de> for TransactionInput<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            private_key: String,
+            record: String,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+
+        let private_key = PrivateKey::<P>::from_str(&raw.private_key).map_err(SerdeError::custom)?;
+        let record = Record::<P>::from_str(&raw.record).map_err(SerdeError::custom)?;
+
+        // Enforce that the decoded record still corresponds to the decoded private key.
+        let address = Address::<P>::from_private_key(&private_key).map_err(SerdeError::custom)?;
+        if &address != record.owner() {
+            return Err(SerdeError::custom("record does not belong to the given private key"));
+        }
+
+        Ok(Self { private_key, record })
+    }
 }
 
 #[derive(Clone, Debug)]
-pub struct TransactionOutput {
-    pub(crate) recipient: Address<Testnet1Parameters>,
+pub struct TransactionOutput<P: Parameters> {
+    pub(crate) recipient: Address<P>,
     pub(crate) amount: u64,
-    // TODO (raychu86): Add support for payloads and birth/death program ids.
-    // pub(crate) payload: Option<Vec<u8>>,
+    // TODO (raychu86): Add support for birth/death program ids.
+    pub(crate) payload: Option<Payload>,
+}
+
+impl<P: Parameters> Serialize for TransactionOutput<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+
+        let payload_bytes = self
+            .payload
+            .as_ref()
+            .map(|payload| to_bytes_le![payload])
+            .transpose()
+            .map_err(S::Error::custom)?;
+
+        let mut state = serializer.serialize_struct("TransactionOutput", 3)?;
+        state.serialize_field("recipient", &self.recipient.to_string())?;
+        state.serialize_field("amount", &self.amount)?;
+        state.serialize_field("payload", &payload_bytes)?;
+        state.end()
+    }
+}
+
+impl<'de, P: Parameters> Deserialize<'de> for TransactionOutput<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            recipient: String,
+            amount: u64,
+            payload: Option<Vec<u8>>,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+        let recipient = Address::<P>::from_str(&raw.recipient).map_err(SerdeError::custom)?;
+        let payload = raw
+            .payload
+            .map(|bytes| Payload::read_le(&bytes[..]))
+            .transpose()
+            .map_err(SerdeError::custom)?;
+
+        Ok(Self {
+            recipient,
+            amount: raw.amount,
+            payload,
+        })
+    }
+}
+
+pub struct TransactionAuthorization<P: Parameters> {
+    pub(crate) authorization: TransactionAuthorizationNative<P>,
 }
 
-pub struct TransactionAuthorization {
-    pub(crate) authorization: TransactionAuthorizationNative<Testnet1Parameters>,
+impl<P: Parameters> Serialize for TransactionAuthorization<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.authorization.to_string())
+    }
+}
+
+impl<'de, P: Parameters> Deserialize<'de> for TransactionAuthorization<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        Self::from_str(&encoded).map_err(SerdeError::custom)
+    }
 }
 
-impl TransactionAuthorization {
+impl<P: Parameters> TransactionAuthorization<P> {
     /// Returns an offline transaction authorization
     pub(crate) fn new<R: Rng + CryptoRng>(
-        spenders: Vec<PrivateKey<Testnet1Parameters>>,
-        records_to_spend: Vec<Record<Testnet1Parameters>>,
-        recipients: Vec<Address<Testnet1Parameters>>,
+        spenders: Vec<PrivateKey<P>>,
+        records_to_spend: Vec<Record<P>>,
+        recipients: Vec<Address<P>>,
         recipient_amounts: Vec<u64>,
-        _network_id: u8, // TODO (howardwu): Keep this around to use for network modularization.
+        recipient_payloads: Vec<Payload>,
+        network_id: u8,
         memo: Option<[u8; 64]>,
         rng: &mut R,
     ) -> Result<Self, DPCError> {
-        let dpc = <Testnet1DPC as DPCScheme<Testnet1Parameters>>::load(false).unwrap();
+        // Enforce that the requested network id matches the network id of the selected parameters.
+        if network_id != P::NETWORK_ID {
+            return Err(DPCError::Message(format!(
+                "Network id {} does not match the network id {} of the selected parameters",
+                network_id,
+                P::NETWORK_ID
+            )));
+        }
+
+        let dpc = <DPC<P> as DPCScheme<P>>::load(false).unwrap();
 
         assert!(!spenders.is_empty());
         assert_eq!(spenders.len(), records_to_spend.len());
 
         assert!(!recipients.is_empty());
         assert_eq!(recipients.len(), recipient_amounts.len());
+        assert_eq!(recipients.len(), recipient_payloads.len());
 
         // Construct the new records
         let mut input_records = vec![];
@@ -82,74 +190,77 @@ impl TransactionAuthorization {
             private_keys.push(private_key);
         }
 
-        while input_records.len() < Testnet1Parameters::NUM_INPUT_RECORDS {
+        while input_records.len() < P::NUM_INPUT_RECORDS {
             let private_key = private_keys[0].clone();
-            let address = Address::<Testnet1Parameters>::from_private_key(&private_key)?;
+            let address = Address::<P>::from_private_key(&private_key)?;
 
-            input_records.push(Record::<Testnet1Parameters>::new(
+            input_records.push(Record::<P>::new(
                 &dpc.noop_program,
                 address,
                 true, // The input record is dummy
                 0,
                 Default::default(),
-                Testnet1Parameters::serial_number_nonce_crh().hash(&rng.gen::<[u8; 32]>())?,
+                P::serial_number_nonce_crh().hash(&rng.gen::<[u8; 32]>())?,
                 rng,
             )?);
             private_keys.push(private_key);
         }
 
-        assert_eq!(input_records.len(), Testnet1Parameters::NUM_INPUT_RECORDS);
+        assert_eq!(input_records.len(), P::NUM_INPUT_RECORDS);
 
         // Enforce that the old record addresses correspond with the private keys
         for (private_key, record) in private_keys.iter().zip(&input_records) {
-            let address = Address::<Testnet1Parameters>::from_private_key(private_key)?;
+            let address = Address::<P>::from_private_key(private_key)?;
             assert_eq!(&address, record.owner());
         }
 
-        assert_eq!(input_records.len(), Testnet1Parameters::NUM_INPUT_RECORDS);
-        assert_eq!(private_keys.len(), Testnet1Parameters::NUM_INPUT_RECORDS);
+        assert_eq!(input_records.len(), P::NUM_INPUT_RECORDS);
+        assert_eq!(private_keys.len(), P::NUM_INPUT_RECORDS);
 
         // Decode new recipient data
         let mut new_record_owners = vec![];
         let mut new_is_dummy_flags = vec![];
         let mut new_values = vec![];
-        for (recipient, amount) in recipients.iter().zip(recipient_amounts) {
+        let mut new_payloads = vec![];
+        for ((recipient, amount), payload) in recipients.iter().zip(recipient_amounts).zip(recipient_payloads) {
             new_record_owners.push(recipient.clone());
             new_is_dummy_flags.push(false);
             new_values.push(amount);
+            new_payloads.push(payload);
         }
 
         // Fill any unused new_record indices with dummy output values
-        while new_record_owners.len() < Testnet1Parameters::NUM_OUTPUT_RECORDS {
+        while new_record_owners.len() < P::NUM_OUTPUT_RECORDS {
             new_record_owners.push(new_record_owners[0].clone());
             new_is_dummy_flags.push(true);
             new_values.push(0);
+            new_payloads.push(Payload::default());
         }
 
-        assert_eq!(new_record_owners.len(), Testnet1Parameters::NUM_OUTPUT_RECORDS);
-        assert_eq!(new_is_dummy_flags.len(), Testnet1Parameters::NUM_OUTPUT_RECORDS);
-        assert_eq!(new_values.len(), Testnet1Parameters::NUM_OUTPUT_RECORDS);
+        assert_eq!(new_record_owners.len(), P::NUM_OUTPUT_RECORDS);
+        assert_eq!(new_is_dummy_flags.len(), P::NUM_OUTPUT_RECORDS);
+        assert_eq!(new_values.len(), P::NUM_OUTPUT_RECORDS);
+        assert_eq!(new_payloads.len(), P::NUM_OUTPUT_RECORDS);
 
-        let new_programs = vec![&dpc.noop_program; Testnet1Parameters::NUM_OUTPUT_RECORDS];
-        let new_payloads: Vec<Payload> = vec![Default::default(); Testnet1Parameters::NUM_OUTPUT_RECORDS];
+        let new_programs = vec![&dpc.noop_program; P::NUM_OUTPUT_RECORDS];
 
         // Generate transaction
 
         let mut joint_serial_numbers = vec![];
-        for i in 0..Testnet1Parameters::NUM_INPUT_RECORDS {
+        for i in 0..P::NUM_INPUT_RECORDS {
             let (sn, _) = input_records[i].to_serial_number(&private_keys[i])?;
             joint_serial_numbers.extend_from_slice(&to_bytes_le![sn]?);
         }
 
         let mut output_records = vec![];
-        for j in 0..Testnet1Parameters::NUM_OUTPUT_RECORDS {
+        for j in 0..P::NUM_OUTPUT_RECORDS {
             output_records.push(Record::new_full(
                 new_programs[j],
                 new_record_owners[j].clone(),
                 new_is_dummy_flags[j],
                 new_values[j],
                 new_payloads[j].clone(),
-                (Testnet1Parameters::NUM_OUTPUT_RECORDS + j) as u8,
+                (P::NUM_OUTPUT_RECORDS + j) as u8,
                 joint_serial_numbers.clone(),
                 rng,
             )?);
@@ -170,43 +281,337 @@ impl TransactionAuthorization {
     }
 }
 
-impl FromStr for TransactionAuthorization {
+impl<P: Parameters> FromStr for TransactionAuthorization<P> {
     type Err = DPCError;
 
     fn from_str(transaction_authorization: &str) -> Result<Self, Self::Err> {
         Ok(Self {
-            authorization: TransactionAuthorizationNative::<Testnet1Parameters>::from_str(transaction_authorization)?,
+            authorization: TransactionAuthorizationNative::<P>::from_str(transaction_authorization)?,
         })
     }
 }
 
-impl fmt::Display for TransactionAuthorization {
+impl<P: Parameters> fmt::Display for TransactionAuthorization<P> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.authorization.to_string())
     }
 }
 
+/// Concatenates the encoding of `outputs` and `memo` (no hash is applied), used so every partial authorization
+/// can commit to the exact outputs it is agreeing to spend against.
+fn output_commitment<P: Parameters>(
+    outputs: &[TransactionOutput<P>],
+    memo: &Option<[u8; 64]>,
+) -> Result<Vec<u8>, DPCError> {
+    let mut preimage = vec![];
+    for output in outputs {
+        preimage.extend_from_slice(output.recipient.to_string().as_bytes());
+        preimage.extend_from_slice(&output.amount.to_le_bytes());
+        if let Some(payload) = &output.payload {
+            preimage.extend_from_slice(&to_bytes_le![payload]?);
+        }
+    }
+    if let Some(memo) = memo {
+        preimage.extend_from_slice(memo);
+    }
+    Ok(preimage)
+}
+
+/// One participant's contribution to a multi-party `TransactionAuthorization`: an input
+/// record, its serial number, and a signature committing to the joint inputs and outputs.
+///
+/// Scope: this lets every participant validate the joint transaction and sign off on it
+/// before any private key is shared. It does NOT keep keys fully isolated end to end —
+/// see [`TransactionAuthorization::combine`] for why assembling the final proof still needs
+/// every signer's key in one place.
+#[derive(Clone, Debug)]
+pub struct PartialAuthorization<P: Parameters> {
+    /// The address that owns `record`, recovered from the signer's private key.
+    pub(crate) address: Address<P>,
+    /// The input record this participant is spending.
+    pub(crate) record: Record<P>,
+    /// The serial number derived for `record`, computed locally from the signer's private key.
+    pub(crate) serial_number: Vec<u8>,
+    /// The encoded outputs and memo this participant is agreeing to spend against.
+    pub(crate) output_commitment: Vec<u8>,
+    /// A signature from `address` over the joint serial numbers and `output_commitment`.
+    pub(crate) signature: Vec<u8>,
+}
+
+impl<P: Parameters> Serialize for PartialAuthorization<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PartialAuthorization", 5)?;
+        state.serialize_field("address", &self.address.to_string())?;
+        state.serialize_field("record", &self.record.to_string())?;
+        state.serialize_field("serial_number", &self.serial_number)?;
+        state.serialize_field("output_commitment", &self.output_commitment)?;
+        state.serialize_field("signature", &self.signature)?;
+        state.end()
+    }
+}
+
+impl<'de, P: Parameters> Deserialize<'de> for PartialAuthorization<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            address: String,
+            record: String,
+            serial_number: Vec<u8>,
+            output_commitment: Vec<u8>,
+            signature: Vec<u8>,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
+
+        let address = Address::<P>::from_str(&raw.address).map_err(SerdeError::custom)?;
+        let record = Record::<P>::from_str(&raw.record).map_err(SerdeError::custom)?;
+
+        // Enforce that the decoded record still belongs to the decoded address.
+        if &address != record.owner() {
+            return Err(SerdeError::custom("record does not belong to the given address"));
+        }
+
+        Ok(Self {
+            address,
+            record,
+            serial_number: raw.serial_number,
+            output_commitment: raw.output_commitment,
+            signature: raw.signature,
+        })
+    }
+}
+
+impl<P: Parameters> PartialAuthorization<P> {
+    ///
+    /// Derives this participant's contribution to a multi-party transaction authorization.
+    ///
+    /// `joint_serial_number_preimage` is the concatenation of every participant's serial
+    /// number, in the input order the final authorization will use; every participant must
+    /// agree on this preimage, and on `outputs`/`memo`, out of band before signing.
+    ///
+    pub fn new<R: Rng + CryptoRng>(
+        private_key: &PrivateKey<P>,
+        record: Record<P>,
+        joint_serial_number_preimage: &[u8],
+        outputs: &[TransactionOutput<P>],
+        memo: Option<[u8; 64]>,
+        rng: &mut R,
+    ) -> Result<Self, DPCError> {
+        let address = Address::<P>::from_private_key(private_key)?;
+        if &address != record.owner() {
+            return Err(DPCError::Message(
+                "record does not belong to the given private key".to_string(),
+            ));
+        }
+
+        let (serial_number, _) = record.to_serial_number(private_key)?;
+        let serial_number = to_bytes_le![serial_number]?;
+        let output_commitment = output_commitment(outputs, &memo)?;
+
+        let mut message = joint_serial_number_preimage.to_vec();
+        message.extend_from_slice(&output_commitment);
+        let signature = private_key.sign(&message, rng)?;
+
+        Ok(Self {
+            address,
+            record,
+            serial_number,
+            output_commitment,
+            signature,
+        })
+    }
+}
+
+impl<P: Parameters> TransactionAuthorization<P> {
+    ///
+    /// SCOPE: despite `PartialAuthorization` letting every participant sign off without
+    /// sharing their key, `combine()` itself still requires every signer's `PrivateKey<P>`
+    /// up front (`private_keys`, kept in the same order as `partials`), because the
+    /// underlying `DPCScheme::authorize` routine needs them all in one place to produce the
+    /// SNARK proof. Keys are therefore NOT kept isolated end to end — this only moves key
+    /// handoff to *after* every participant has validated the joint inputs/outputs and
+    /// signed off on them, rather than eliminating the handoff. Delivering true end-to-end
+    /// key isolation needs a snarkVM-level change to how `authorize` is proved (e.g. a
+    /// genuine multi-party proving protocol), which is out of scope for this module.
+    ///
+    /// Merges independently produced `PartialAuthorization`s into a complete transaction
+    /// authorization. Fails unless every partial committed to the same `outputs`/`memo` and
+    /// signed the agreed-upon joint serial numbers and output commitment.
+    ///
+    pub fn combine<R: Rng + CryptoRng>(
+        partials: Vec<PartialAuthorization<P>>,
+        private_keys: Vec<PrivateKey<P>>,
+        outputs: Vec<TransactionOutput<P>>,
+        memo: Option<[u8; 64]>,
+        rng: &mut R,
+    ) -> Result<Self, DPCError> {
+        // Check that the transaction is limited to `P::NUM_INPUT_RECORDS` inputs.
+        if partials.is_empty() || partials.len() > P::NUM_INPUT_RECORDS {
+            return Err(DPCError::InvalidNumberOfInputs(partials.len(), P::NUM_INPUT_RECORDS));
+        }
+        assert_eq!(partials.len(), private_keys.len());
+
+        // Check that the transaction has at least one output and is limited to `P::NUM_OUTPUT_RECORDS` outputs.
+        if outputs.is_empty() || outputs.len() > P::NUM_OUTPUT_RECORDS {
+            return Err(DPCError::InvalidNumberOfOutputs(outputs.len(), P::NUM_OUTPUT_RECORDS));
+        }
+
+        // Recompute the joint serial-number preimage every participant should have signed.
+        let mut joint_serial_number_preimage = vec![];
+        for partial in &partials {
+            joint_serial_number_preimage.extend_from_slice(&partial.serial_number);
+        }
+
+        // Recompute the output commitment for the outputs actually being assembled, and
+        // reject unless every partial committed to exactly this set of outputs and memo.
+        let output_commitment = output_commitment(&outputs, &memo)?;
+        for partial in &partials {
+            if partial.output_commitment != output_commitment {
+                return Err(DPCError::Message(
+                    "Partial authorization does not commit to the given outputs".to_string(),
+                ));
+            }
+        }
+
+        // Verify that every partial signed the agreed-upon joint preimage and output commitment.
+        let mut message = joint_serial_number_preimage;
+        message.extend_from_slice(&output_commitment);
+        for partial in &partials {
+            if !partial.address.verify_signature(&message, &partial.signature)? {
+                return Err(DPCError::Message(format!(
+                    "Invalid partial authorization signature from {}",
+                    partial.address
+                )));
+            }
+        }
+
+        // Verify that the supplied private keys correspond to the partials they are paired with.
+        for (partial, private_key) in partials.iter().zip(&private_keys) {
+            if partial.address != Address::<P>::from_private_key(private_key)? {
+                return Err(DPCError::Message(
+                    "Private key does not match the partial authorization it was paired with".to_string(),
+                ));
+            }
+        }
+
+        let records_to_spend: Vec<_> = partials.into_iter().map(|partial| partial.record).collect();
+
+        let mut recipients = vec![];
+        let mut recipient_amounts = vec![];
+        let mut recipient_payloads = vec![];
+        for output in outputs {
+            recipients.push(output.recipient);
+            recipient_amounts.push(output.amount);
+            recipient_payloads.push(output.payload.unwrap_or_default());
+        }
+
+        Self::new(
+            private_keys,
+            records_to_spend,
+            recipients,
+            recipient_amounts,
+            recipient_payloads,
+            P::NETWORK_ID,
+            memo,
+            rng,
+        )
+    }
+}
+
+/// An error returned while assembling a `TransactionAuthorization` from a
+/// `TransactionAuthorizationBuilder`.
+///
+/// `DPCError` is defined in the external `snarkvm` crate, so it cannot be given an
+/// `InsufficientBalance` variant from here; this wraps it instead. That makes `build()`'s
+/// return type `TransactionAuthorizationBuilderError` rather than `DPCError` — an API break
+/// for any caller that matched on the old error type.
+#[derive(Debug)]
+pub enum TransactionAuthorizationBuilderError {
+    /// The builder's input records do not have enough value to cover the requested outputs and fee.
+    InsufficientBalance { have: u64, need: u64 },
+    /// A lower-level DPC error occurred while assembling the authorization.
+    DPCError(DPCError),
+}
+
+impl fmt::Display for TransactionAuthorizationBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InsufficientBalance { have, need } => write!(
+                f,
+                "insufficient balance to build transaction authorization: have {}, need {}",
+                have, need
+            ),
+            Self::DPCError(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for TransactionAuthorizationBuilderError {}
+
+impl From<DPCError> for TransactionAuthorizationBuilderError {
+    fn from(error: DPCError) -> Self {
+        Self::DPCError(error)
+    }
+}
+
+/// Serializes and deserializes an `Option<Address<P>>` using its canonical bech32 encoding.
+mod address_option {
+    use super::*;
+
+    pub fn serialize<P: Parameters, S: Serializer>(
+        value: &Option<Address<P>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.as_ref().map(|address| address.to_string()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, P: Parameters, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Address<P>>, D::Error> {
+        let encoded = Option::<String>::deserialize(deserializer)?;
+        encoded
+            .map(|encoded| Address::<P>::from_str(&encoded).map_err(SerdeError::custom))
+            .transpose()
+    }
+}
+
 // TODO (raychu86) Look into genericizing this model into `dpc`.
-#[derive(Clone, Debug, Default)]
-pub struct TransactionAuthorizationBuilder {
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "P: Parameters", deserialize = "P: Parameters"))]
+pub struct TransactionAuthorizationBuilder<P: Parameters> {
     /// Transaction inputs
-    pub(crate) inputs: Vec<TransactionInput>,
+    pub(crate) inputs: Vec<TransactionInput<P>>,
     /// Transaction outputs
-    pub(crate) outputs: Vec<TransactionOutput>,
+    pub(crate) outputs: Vec<TransactionOutput<P>>,
     /// Network ID
     pub(crate) network_id: u8,
     /// Transaction memo
     pub(crate) memo: Option<[u8; 64]>,
+    /// Explicit transaction fee, subtracted from the input value before computing change.
+    pub(crate) fee: u64,
+    /// The address that receives any unspent input value as a change output.
+    #[serde(with = "address_option")]
+    pub(crate) change_address: Option<Address<P>>,
+    #[serde(skip)]
+    pub(crate) _parameters: PhantomData<P>,
+}
+
+impl<P: Parameters> Default for TransactionAuthorizationBuilder<P> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl TransactionAuthorizationBuilder {
+impl<P: Parameters> TransactionAuthorizationBuilder<P> {
     pub fn new() -> Self {
-        // TODO (raychu86) update the default to `0` for mainnet.
         Self {
             inputs: vec![],
             outputs: vec![],
-            network_id: Testnet1Parameters::NETWORK_ID,
+            network_id: P::NETWORK_ID,
             memo: None,
+            fee: 0,
+            change_address: None,
+            _parameters: PhantomData,
         }
     }
 
@@ -214,17 +619,10 @@ impl TransactionAuthorizationBuilder {
     /// Returns a new transaction builder with the added transaction input.
     /// Otherwise, returns a `DPCError`.
     ///
-    pub fn add_input(
-        self,
-        private_key: PrivateKey<Testnet1Parameters>,
-        record: Record<Testnet1Parameters>,
-    ) -> Result<Self, DPCError> {
-        // Check that the transaction is limited to `Testnet1Parameters::NUM_INPUT_RECORDS` inputs.
-        if self.inputs.len() > Testnet1Parameters::NUM_INPUT_RECORDS {
-            return Err(DPCError::InvalidNumberOfInputs(
-                self.inputs.len() + 1,
-                Testnet1Parameters::NUM_INPUT_RECORDS,
-            ));
+    pub fn add_input(self, private_key: PrivateKey<P>, record: Record<P>) -> Result<Self, DPCError> {
+        // Check that the transaction is limited to `P::NUM_INPUT_RECORDS` inputs.
+        if self.inputs.len() >= P::NUM_INPUT_RECORDS {
+            return Err(DPCError::InvalidNumberOfInputs(self.inputs.len() + 1, P::NUM_INPUT_RECORDS));
         }
 
         // Construct the transaction input.
@@ -241,17 +639,46 @@ impl TransactionAuthorizationBuilder {
     /// Returns a new transaction builder with the added transaction output.
     /// Otherwise, returns a `DPCError`.
     ///
-    pub fn add_output(self, recipient: Address<Testnet1Parameters>, amount: u64) -> Result<Self, DPCError> {
-        // Check that the transaction is limited to `Testnet1Parameters::NUM_OUTPUT_RECORDS` outputs.
-        if self.outputs.len() > Testnet1Parameters::NUM_OUTPUT_RECORDS {
-            return Err(DPCError::InvalidNumberOfOutputs(
-                self.outputs.len() + 1,
-                Testnet1Parameters::NUM_OUTPUT_RECORDS,
-            ));
+    pub fn add_output(self, recipient: Address<P>, amount: u64) -> Result<Self, DPCError> {
+        self.add_output_with_payload(recipient, amount, vec![])
+    }
+
+    ///
+    /// Returns a new transaction builder with the added transaction output, attaching the
+    /// given record payload.
+    /// Otherwise, returns a `DPCError`.
+    ///
+    pub fn add_output_with_payload(
+        self,
+        recipient: Address<P>,
+        amount: u64,
+        payload: Vec<u8>,
+    ) -> Result<Self, DPCError> {
+        // Check that the transaction is limited to `P::NUM_OUTPUT_RECORDS` outputs.
+        if self.outputs.len() >= P::NUM_OUTPUT_RECORDS {
+            return Err(DPCError::InvalidNumberOfOutputs(self.outputs.len() + 1, P::NUM_OUTPUT_RECORDS));
+        }
+
+        // Check that the payload fits within the record payload capacity.
+        if payload.len() > P::PAYLOAD_SIZE_IN_BYTES {
+            return Err(DPCError::Message(format!(
+                "Output payload of {} bytes exceeds the maximum record payload capacity of {} bytes",
+                payload.len(),
+                P::PAYLOAD_SIZE_IN_BYTES
+            )));
         }
 
         // Construct the transaction output.
-        let output = TransactionOutput { recipient, amount };
+        let payload = if payload.is_empty() {
+            None
+        } else {
+            Some(Payload::from_bytes_le(&payload))
+        };
+        let output = TransactionOutput {
+            recipient,
+            amount,
+            payload,
+        };
 
         // Update the current builder instance.
         let mut builder = self;
@@ -278,38 +705,43 @@ impl TransactionAuthorizationBuilder {
         builder
     }
 
+    ///
+    /// Returns a new transaction builder with the updated explicit transaction fee.
+    ///
+    pub fn fee(self, fee: u64) -> Self {
+        let mut builder = self;
+        builder.fee = fee;
+        builder
+    }
+
+    ///
+    /// Returns a new transaction builder that sends any unspent input value to `change_address`
+    /// as a change output.
+    ///
+    pub fn change_address(self, change_address: Address<P>) -> Self {
+        let mut builder = self;
+        builder.change_address = Some(change_address);
+        builder
+    }
+
     ///
     /// Returns the transaction authorization derived from the provided builder
     /// attributes.
     ///
-    /// Otherwise, returns `DPCError`.
+    /// Otherwise, returns `TransactionAuthorizationBuilderError`.
     ///
-    pub fn build<R: Rng + CryptoRng>(&self, rng: &mut R) -> Result<TransactionAuthorization, DPCError> {
-        // Check that the transaction is limited to `Testnet1Parameters::NUM_INPUT_RECORDS` inputs.
-        match self.inputs.len() {
-            1 | 2 => {}
-            num_inputs => {
-                return Err(DPCError::InvalidNumberOfInputs(
-                    num_inputs,
-                    Testnet1Parameters::NUM_INPUT_RECORDS,
-                ));
-            }
+    pub fn build<R: Rng + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<TransactionAuthorization<P>, TransactionAuthorizationBuilderError> {
+        // Check that the transaction has at least one input and is limited to `P::NUM_INPUT_RECORDS` inputs.
+        if self.inputs.is_empty() || self.inputs.len() > P::NUM_INPUT_RECORDS {
+            return Err(DPCError::InvalidNumberOfInputs(self.inputs.len(), P::NUM_INPUT_RECORDS).into());
         }
 
-        // Check that the transaction has at least one output and is limited to `Testnet1Parameters::NUM_OUTPUT_RECORDS` outputs.
-        match self.outputs.len() {
-            0 => {
-                return Err(DPCError::Message(
-                    "Transaction authorization is missing outputs".to_string(),
-                ));
-            }
-            1 | 2 => {}
-            num_inputs => {
-                return Err(DPCError::InvalidNumberOfInputs(
-                    num_inputs,
-                    Testnet1Parameters::NUM_INPUT_RECORDS,
-                ));
-            }
+        // Check that the transaction has at least one output and is limited to `P::NUM_OUTPUT_RECORDS` outputs.
+        if self.outputs.is_empty() || self.outputs.len() > P::NUM_OUTPUT_RECORDS {
+            return Err(DPCError::InvalidNumberOfOutputs(self.outputs.len(), P::NUM_OUTPUT_RECORDS).into());
         }
 
         // Construct the parameters from the given transaction inputs.
@@ -321,24 +753,372 @@ impl TransactionAuthorizationBuilder {
             records_to_spend.push(input.record.clone());
         }
 
+        // Sum the plaintext value of the input records being spent.
+        let total_input_value: u64 = records_to_spend.iter().map(|record| record.value()).sum();
+
+        // Sum the requested output amounts, plus the explicit fee.
+        let total_output_value: u64 = self.outputs.iter().map(|output| output.amount).sum();
+        let total_spend_value = total_output_value.checked_add(self.fee).unwrap_or(u64::MAX);
+
+        // Determine the unspent remainder, erroring if the inputs do not cover the spend.
+        let change_value = total_input_value.checked_sub(total_spend_value).ok_or(
+            TransactionAuthorizationBuilderError::InsufficientBalance {
+                have: total_input_value,
+                need: total_spend_value,
+            },
+        )?;
+
         // Construct the parameters from the given transaction outputs.
         let mut recipients = vec![];
         let mut recipient_amounts = vec![];
+        let mut recipient_payloads = vec![];
 
         for output in &self.outputs {
             recipients.push(output.recipient.clone());
             recipient_amounts.push(output.amount);
+            recipient_payloads.push(output.payload.clone().unwrap_or_default());
+        }
+
+        // Append a change output for any unspent remainder, rejecting the build if there is
+        // nowhere configured to send it.
+        if change_value > 0 {
+            match &self.change_address {
+                Some(change_address) => {
+                    if recipients.len() >= P::NUM_OUTPUT_RECORDS {
+                        return Err(
+                            DPCError::InvalidNumberOfOutputs(recipients.len() + 1, P::NUM_OUTPUT_RECORDS).into(),
+                        );
+                    }
+
+                    recipients.push(change_address.clone());
+                    recipient_amounts.push(change_value);
+                    recipient_payloads.push(Payload::default());
+                }
+                None => {
+                    return Err(DPCError::Message(format!(
+                        "Transaction has an unspent balance of {} but no change address was set",
+                        change_value
+                    ))
+                    .into());
+                }
+            }
         }
 
         // Construct the transaction authorization
-        TransactionAuthorization::new(
+        Ok(TransactionAuthorization::new(
             spenders,
             records_to_spend,
             recipients,
             recipient_amounts,
+            recipient_payloads,
             self.network_id,
             self.memo,
             rng,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::dpc::testnet1::Testnet1Parameters;
+
+    use rand::thread_rng;
+
+    fn test_dpc() -> DPC<Testnet1Parameters> {
+        <DPC<Testnet1Parameters> as DPCScheme<Testnet1Parameters>>::load(false).unwrap()
+    }
+
+    fn test_account<R: Rng + CryptoRng>(rng: &mut R) -> (PrivateKey<Testnet1Parameters>, Address<Testnet1Parameters>) {
+        let private_key = PrivateKey::<Testnet1Parameters>::new(rng);
+        let address = Address::from_private_key(&private_key).unwrap();
+        (private_key, address)
+    }
+
+    fn test_record<R: Rng + CryptoRng>(
+        dpc: &DPC<Testnet1Parameters>,
+        owner: Address<Testnet1Parameters>,
+        value: u64,
+        rng: &mut R,
+    ) -> Record<Testnet1Parameters> {
+        Record::<Testnet1Parameters>::new(
+            &dpc.noop_program,
+            owner,
+            false,
+            value,
+            Default::default(),
+            Testnet1Parameters::serial_number_nonce_crh()
+                .hash(&rng.gen::<[u8; 32]>())
+                .unwrap(),
+            rng,
         )
+        .unwrap()
+    }
+
+    #[test]
+    fn transaction_input_round_trips_through_json() {
+        let rng = &mut thread_rng();
+        let dpc = test_dpc();
+
+        let (private_key, address) = test_account(rng);
+        let record = test_record(&dpc, address, 10, rng);
+        let input = TransactionInput { private_key, record };
+
+        let json = serde_json::to_string(&input).unwrap();
+        let decoded: TransactionInput<Testnet1Parameters> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(input.private_key.to_string(), decoded.private_key.to_string());
+        assert_eq!(input.record.to_string(), decoded.record.to_string());
+    }
+
+    #[test]
+    fn transaction_input_deserialize_rejects_mismatched_record() {
+        let rng = &mut thread_rng();
+        let dpc = test_dpc();
+
+        let (private_key, _) = test_account(rng);
+        let (_, other_address) = test_account(rng);
+        let record = test_record(&dpc, other_address, 10, rng);
+
+        let json = format!(
+            "{{\"private_key\":{},\"record\":{}}}",
+            serde_json::to_string(&private_key.to_string()).unwrap(),
+            serde_json::to_string(&record.to_string()).unwrap()
+        );
+
+        assert!(serde_json::from_str::<TransactionInput<Testnet1Parameters>>(&json).is_err());
+    }
+
+    #[test]
+    fn transaction_output_round_trips_through_json() {
+        let rng = &mut thread_rng();
+        let (_, recipient) = test_account(rng);
+
+        let output = TransactionOutput {
+            recipient,
+            amount: 42,
+            payload: None,
+        };
+
+        let json = serde_json::to_string(&output).unwrap();
+        let decoded: TransactionOutput<Testnet1Parameters> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(output.recipient.to_string(), decoded.recipient.to_string());
+        assert_eq!(output.amount, decoded.amount);
+        assert!(decoded.payload.is_none());
+    }
+
+    #[test]
+    fn transaction_authorization_builder_round_trips_through_json() {
+        let rng = &mut thread_rng();
+        let dpc = test_dpc();
+
+        let (spender_key, spender_address) = test_account(rng);
+        let (_, recipient_address) = test_account(rng);
+        let (_, change_address) = test_account(rng);
+        let input_record = test_record(&dpc, spender_address, 100, rng);
+
+        let builder = TransactionAuthorizationBuilder::<Testnet1Parameters>::new()
+            .add_input(spender_key, input_record)
+            .unwrap()
+            .add_output(recipient_address, 30)
+            .unwrap()
+            .fee(10)
+            .change_address(change_address);
+
+        let json = serde_json::to_string(&builder).unwrap();
+        let decoded: TransactionAuthorizationBuilder<Testnet1Parameters> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded.inputs.len(), builder.inputs.len());
+        assert_eq!(decoded.outputs.len(), builder.outputs.len());
+        assert_eq!(decoded.network_id, builder.network_id);
+        assert_eq!(decoded.fee, builder.fee);
+        assert_eq!(
+            decoded.change_address.map(|a| a.to_string()),
+            builder.change_address.map(|a| a.to_string())
+        );
+    }
+
+    #[test]
+    fn add_output_with_payload_rejects_oversized_payload() {
+        let rng = &mut thread_rng();
+        let (_, recipient_address) = test_account(rng);
+
+        let oversized_payload = vec![0u8; Testnet1Parameters::PAYLOAD_SIZE_IN_BYTES + 1];
+
+        let result = TransactionAuthorizationBuilder::<Testnet1Parameters>::new().add_output_with_payload(
+            recipient_address,
+            10,
+            oversized_payload,
+        );
+
+        assert!(matches!(result, Err(DPCError::Message(_))));
+    }
+
+    #[test]
+    fn build_rejects_insufficient_balance() {
+        let rng = &mut thread_rng();
+        let dpc = test_dpc();
+
+        let (spender_key, spender_address) = test_account(rng);
+        let (_, recipient_address) = test_account(rng);
+        let input_record = test_record(&dpc, spender_address, 10, rng);
+
+        let builder = TransactionAuthorizationBuilder::<Testnet1Parameters>::new()
+            .add_input(spender_key, input_record)
+            .unwrap()
+            .add_output(recipient_address, 30)
+            .unwrap();
+
+        match builder.build(rng) {
+            Err(TransactionAuthorizationBuilderError::InsufficientBalance { have, need }) => {
+                assert_eq!(have, 10);
+                assert_eq!(need, 30);
+            }
+            _ => panic!("expected InsufficientBalance"),
+        }
+    }
+
+    #[test]
+    fn build_appends_change_output_when_configured() {
+        let rng = &mut thread_rng();
+        let dpc = test_dpc();
+
+        let (spender_key, spender_address) = test_account(rng);
+        let (_, recipient_address) = test_account(rng);
+        let (_, change_address) = test_account(rng);
+        let input_record = test_record(&dpc, spender_address, 100, rng);
+
+        let builder = TransactionAuthorizationBuilder::<Testnet1Parameters>::new()
+            .add_input(spender_key, input_record)
+            .unwrap()
+            .add_output(recipient_address, 30)
+            .unwrap()
+            .change_address(change_address);
+
+        assert!(builder.build(rng).is_ok());
+    }
+
+    #[test]
+    fn build_rejects_unspent_remainder_without_change_address() {
+        let rng = &mut thread_rng();
+        let dpc = test_dpc();
+
+        let (spender_key, spender_address) = test_account(rng);
+        let (_, recipient_address) = test_account(rng);
+        let input_record = test_record(&dpc, spender_address, 100, rng);
+
+        let builder = TransactionAuthorizationBuilder::<Testnet1Parameters>::new()
+            .add_input(spender_key, input_record)
+            .unwrap()
+            .add_output(recipient_address, 30)
+            .unwrap();
+
+        assert!(matches!(
+            builder.build(rng),
+            Err(TransactionAuthorizationBuilderError::DPCError(_))
+        ));
+    }
+
+    #[test]
+    fn build_allows_exact_balance_without_change_address() {
+        let rng = &mut thread_rng();
+        let dpc = test_dpc();
+
+        let (spender_key, spender_address) = test_account(rng);
+        let (_, recipient_address) = test_account(rng);
+        let input_record = test_record(&dpc, spender_address, 30, rng);
+
+        let builder = TransactionAuthorizationBuilder::<Testnet1Parameters>::new()
+            .add_input(spender_key, input_record)
+            .unwrap()
+            .add_output(recipient_address, 30)
+            .unwrap();
+
+        assert!(builder.build(rng).is_ok());
+    }
+
+    #[test]
+    fn build_fee_exactly_exhausts_remainder_without_change_address() {
+        let rng = &mut thread_rng();
+        let dpc = test_dpc();
+
+        let (spender_key, spender_address) = test_account(rng);
+        let (_, recipient_address) = test_account(rng);
+        let input_record = test_record(&dpc, spender_address, 100, rng);
+
+        // input (100) == output (80) + fee (20), so there is no change to account for.
+        let builder = TransactionAuthorizationBuilder::<Testnet1Parameters>::new()
+            .add_input(spender_key, input_record)
+            .unwrap()
+            .add_output(recipient_address, 80)
+            .unwrap()
+            .fee(20);
+
+        assert!(builder.build(rng).is_ok());
+    }
+
+    #[test]
+    fn build_fee_is_subtracted_from_available_balance() {
+        let rng = &mut thread_rng();
+        let dpc = test_dpc();
+
+        let (spender_key, spender_address) = test_account(rng);
+        let (_, recipient_address) = test_account(rng);
+        let input_record = test_record(&dpc, spender_address, 100, rng);
+
+        // input (100) < output (80) + fee (21), even though input > output alone, so the fee
+        // must be the reason the build is rejected as short by exactly 1.
+        let builder = TransactionAuthorizationBuilder::<Testnet1Parameters>::new()
+            .add_input(spender_key, input_record)
+            .unwrap()
+            .add_output(recipient_address, 80)
+            .unwrap()
+            .fee(21);
+
+        match builder.build(rng) {
+            Err(TransactionAuthorizationBuilderError::InsufficientBalance { have, need }) => {
+                assert_eq!(have, 100);
+                assert_eq!(need, 101);
+            }
+            _ => panic!("expected InsufficientBalance"),
+        }
+    }
+
+    #[test]
+    fn combine_rejects_forged_signature() {
+        let rng = &mut thread_rng();
+        let dpc = test_dpc();
+
+        let (spender_key, spender_address) = test_account(rng);
+        let (forger_key, _) = test_account(rng);
+        let (_, recipient_address) = test_account(rng);
+        let input_record = test_record(&dpc, spender_address, 30, rng);
+
+        let output = TransactionOutput {
+            recipient: recipient_address,
+            amount: 30,
+            payload: None,
+        };
+
+        let (serial_number, _) = input_record.to_serial_number(&spender_key).unwrap();
+        let joint_serial_number_preimage = to_bytes_le![serial_number].unwrap();
+
+        let mut partial = PartialAuthorization::new(
+            &spender_key,
+            input_record,
+            &joint_serial_number_preimage,
+            std::slice::from_ref(&output),
+            None,
+            rng,
+        )
+        .unwrap();
+
+        // Tamper with the signature so it no longer matches what this participant signed.
+        partial.signature = forger_key.sign(b"forged", rng).unwrap();
+
+        let result = TransactionAuthorization::combine(vec![partial], vec![spender_key], vec![output], None, rng);
+
+        assert!(result.is_err());
     }
 }